@@ -1,3 +1,5 @@
+use std::collections::{BTreeSet, HashMap};
+
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,54 +34,283 @@ enum State {
     Intermediate,
 }
 
+/// Equivalence-class table derived from the transition table so that
+/// advancing the DFA by one character is O(1) rather than a scan over a
+/// state's edges. Every character that appears in some `MatchChar` edge
+/// gets a unique class id; every other character is behaviorally
+/// identical (it can only take a `MatchAny`/`MatchEow` edge) and shares
+/// one "other" class. The table is rebuilt from `transitions` whenever a
+/// `Cylon` is compiled or deserialized, and is never serialized itself.
+#[derive(Debug, Default)]
+struct Classes {
+    // Class id for each character that appears in a MatchChar edge.
+    class_of: HashMap<char, u32>,
+    // The shared class id for every character outside that set.
+    other: u32,
+    // Per state, the next state for each class id (indexed by class id),
+    // defaulting to the state's MatchAny target.
+    rows: Vec<Vec<usize>>,
+}
+
+impl Classes {
+    fn build(transitions: &[Vec<Transition>]) -> Self {
+        // Gather the set of characters that appear in any MatchChar edge,
+        // assigning each a unique class id in first-seen order.
+        let mut class_of: HashMap<char, u32> = HashMap::new();
+        for t in transitions {
+            for transition in t {
+                if let Transition(Edge::MatchChar(edge_char), ..) = transition {
+                    let next = class_of.len() as u32;
+                    class_of.entry(*edge_char).or_insert(next);
+                }
+            }
+        }
+        let other = class_of.len() as u32;
+        let width = other as usize + 1;
+
+        let rows = transitions
+            .iter()
+            .map(|t| {
+                // Default every class (including "other") to the MatchAny
+                // target, which every state is guaranteed to have. A state
+                // may carry more than one MatchAny edge (a `*` child
+                // overrides the inherited wildcard state); the last one
+                // wins, matching the reverse scan that `allow` performed.
+                let any = t
+                    .iter()
+                    .rev()
+                    .find_map(|transition| match transition {
+                        Transition(Edge::MatchAny, next_state) => Some(*next_state),
+                        _ => None,
+                    })
+                    .unwrap();
+                let mut row = vec![any; width];
+                // Then let specific MatchChar edges win over MatchAny,
+                // preserving the priority rule baked into `allow`.
+                for transition in t {
+                    if let Transition(Edge::MatchChar(edge_char), next_state) = transition {
+                        row[class_of[edge_char] as usize] = *next_state;
+                    }
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            class_of,
+            other,
+            rows,
+        }
+    }
+
+    /// Advance from `state` on `path_char` in O(1).
+    #[inline]
+    fn step(&self, state: usize, path_char: char) -> usize {
+        let class = self.class_of.get(&path_char).copied().unwrap_or(self.other);
+        self.rows[state][class as usize]
+    }
+}
+
 /// A Cylon is a DFA that recognizes rules from a compiled robots.txt
 /// file. By providing it a URL path, it can decide whether or not
 /// the robots file that compiled it allows or disallows that path in
 /// roughly O(n) time, where n is the length of the path.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "CylonRepr")]
 pub struct Cylon {
     states: Vec<State>,
     transitions: Vec<Vec<Transition>>,
+    // The originating patterns, tagged with whether each was an `Allow`,
+    // retained verbatim so `allow_rfc9309` can score a path against every
+    // matching rule rather than a single deterministic DFA walk.
+    rules: Vec<PatternRule>,
+    // Derived from `transitions`; see `Classes`.
+    #[serde(skip)]
+    classes: Classes,
+}
+
+/// An owned, matchable copy of a compiled rule: its pattern plus whether
+/// it was an `Allow`. Kept alongside the DFA so RFC 9309 most-specific
+/// matching can compare every rule that matches a path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PatternRule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The serialized form of a `Cylon`. Only the states, transitions and
+/// rules are persisted; the equivalence-class table is derived from them
+/// when a `Cylon` is reconstructed.
+#[derive(Deserialize)]
+struct CylonRepr {
+    states: Vec<State>,
+    transitions: Vec<Vec<Transition>>,
+    // Absent in machines serialized before RFC 9309 scoring was added;
+    // such payloads simply have no rules to score against.
+    #[serde(default)]
+    rules: Vec<PatternRule>,
+}
+
+impl From<CylonRepr> for Cylon {
+    fn from(repr: CylonRepr) -> Self {
+        Cylon::from_parts(repr.states, repr.transitions, repr.rules)
+    }
 }
 
 impl Cylon {
+    /// Assemble a `Cylon` from its states, transitions and originating
+    /// rules, deriving the equivalence-class table that `allow` matches
+    /// against.
+    fn from_parts(
+        states: Vec<State>,
+        transitions: Vec<Vec<Transition>>,
+        rules: Vec<PatternRule>,
+    ) -> Self {
+        let classes = Classes::build(&transitions);
+        Self {
+            states,
+            transitions,
+            rules,
+            classes,
+        }
+    }
+
     /// Match whether the rules allow or disallow the target path.
     pub fn allow(&self, path: &str) -> bool {
-        let mut state = path.chars().fold(2, |state, path_char| {
-            let t = &self.transitions[state];
-            t.iter()
-                .rev()
-                // Pick the last transition to always prioritize MatchChar
-                // over MatchAny (which will always be the first transition.)
-                .find(|transition| match transition {
-                    Transition(Edge::MatchAny, ..) => true,
-                    Transition(Edge::MatchEow, ..) => false,
-                    Transition(Edge::MatchChar(edge_char), ..) => *edge_char == path_char,
-                })
-                .map(|Transition(.., next_state)| *next_state)
-                // We are guaranteed at least one matching state because of
-                // the way the DFA is constructed.
-                .unwrap()
-        });
+        let mut matcher = self.matcher();
+        for path_char in path.chars() {
+            matcher.push(path_char);
+        }
+        matcher.allowed()
+    }
 
-        // Follow the EoW transition, if necessary
-        let t = &self.transitions[state];
-        state = t
-            .iter()
-            .rev()
-            .find(|transition| match transition {
-                Transition(Edge::MatchEow, ..) => true,
-                Transition(Edge::MatchAny, ..) => true,
-                _ => false,
-            })
-            .map(|Transition(.., next_state)| *next_state)
-            .unwrap_or(state);
+    /// Match a raw URL path, normalizing it the way the robots spec
+    /// requires before feeding it to the DFA. Percent escapes that do not
+    /// encode a reserved delimiter are decoded (so `/a%62c` matches the
+    /// same rules as `/abc`), any escape that is left encoded has its hex
+    /// digits upper-cased, `%2F` is kept distinct from `/`, `.`/`..`
+    /// segments are collapsed per RFC 3986, and a leading `/` is inserted
+    /// if missing. Callers that already work with normalized paths can
+    /// keep using [`Cylon::allow`].
+    pub fn allow_url(&self, path: &str) -> bool {
+        self.allow(&Self::normalize(path))
+    }
 
-        match self.states[state] {
-            State::Allow => true,
-            State::Disallow => false,
-            // Intermediate states are not preserved in the DFA
-            State::Intermediate => unreachable!(),
+    /// Normalize a raw URL path into the `char` stream the DFA matches
+    /// against. See [`Cylon::allow_url`] for the rules applied.
+    fn normalize(path: &str) -> String {
+        fn hex_val(b: u8) -> Option<u8> {
+            match b {
+                b'0'..=b'9' => Some(b - b'0'),
+                b'a'..=b'f' => Some(b - b'a' + 10),
+                b'A'..=b'F' => Some(b - b'A' + 10),
+                _ => None,
+            }
+        }
+
+        // Reserved delimiters (the RFC 3986 gen-delims and sub-delims) must
+        // stay percent-encoded, since decoding them would change how the
+        // path is split. `%2F` in particular is not the same as a literal
+        // `/` for matching purposes.
+        fn is_reserved(byte: u8) -> bool {
+            b":/?#[]@!$&'()*+,;=".contains(&byte)
+        }
+
+        let bytes = path.as_bytes();
+        let mut out: Vec<u8> = Vec::with_capacity(bytes.len() + 1);
+        if bytes.first() != Some(&b'/') {
+            out.push(b'/');
+        }
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            // A percent escape is `%` followed by two hex digits.
+            let decoded = if byte == b'%' && i + 2 < bytes.len() {
+                match (hex_val(bytes[i + 1]), hex_val(bytes[i + 2])) {
+                    (Some(hi), Some(lo)) => Some(hi * 16 + lo),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            if let Some(decoded) = decoded {
+                if is_reserved(decoded) {
+                    // Leave the escape in place, but canonicalize its
+                    // hex digits to upper case.
+                    out.push(b'%');
+                    out.push(bytes[i + 1].to_ascii_uppercase());
+                    out.push(bytes[i + 2].to_ascii_uppercase());
+                } else {
+                    out.push(decoded);
+                }
+                i += 3;
+                continue;
+            }
+            out.push(byte);
+            i += 1;
+        }
+
+        Self::remove_dot_segments(&String::from_utf8_lossy(&out))
+    }
+
+    /// Collapse `.` and `..` path segments per RFC 3986 §5.2.4 so that
+    /// `/a/./b` and `/a/../b` match the same rules as `/a/b` and `/b`.
+    /// Only the `.` and `..` segments are removed; interior empty
+    /// segments are preserved (`/a//b` stays `/a//b`). The path is
+    /// already absolute (a leading `/` was inserted by `normalize`), and
+    /// a preserved `%2F` is left untouched since it is not a separator.
+    fn remove_dot_segments(path: &str) -> String {
+        // Drop the last segment (and its preceding `/`) already moved to
+        // the output buffer, as `..` requires.
+        fn remove_last_segment(output: &mut String) {
+            match output.rfind('/') {
+                Some(idx) => output.truncate(idx),
+                None => output.clear(),
+            }
+        }
+
+        let mut input = path.to_string();
+        let mut output = String::with_capacity(path.len());
+        while !input.is_empty() {
+            if let Some(rest) = input.strip_prefix("../") {
+                input = rest.to_string();
+            } else if let Some(rest) = input.strip_prefix("./") {
+                input = rest.to_string();
+            } else if let Some(rest) = input.strip_prefix("/./") {
+                input = format!("/{rest}");
+            } else if input == "/." {
+                input = "/".to_string();
+            } else if let Some(rest) = input.strip_prefix("/../") {
+                input = format!("/{rest}");
+                remove_last_segment(&mut output);
+            } else if input == "/.." {
+                input = "/".to_string();
+                remove_last_segment(&mut output);
+            } else if input == "." || input == ".." {
+                input.clear();
+            } else {
+                // Move the first path segment (the leading `/`, if any,
+                // plus everything up to the next `/`) to the output.
+                let start = usize::from(input.starts_with('/'));
+                let end = match input[start..].find('/') {
+                    Some(idx) => start + idx,
+                    None => input.len(),
+                };
+                output.push_str(&input[..end]);
+                input = input[end..].to_string();
+            }
+        }
+        output
+    }
+
+    /// Begin an incremental match against this `Cylon`, positioned at the
+    /// root state. Feed the path one character at a time with
+    /// [`Matcher::push`] and read the verdict with [`Matcher::allowed`].
+    pub fn matcher(&self) -> Matcher {
+        Matcher {
+            cylon: self,
+            state: 2,
         }
     }
 
@@ -96,6 +327,16 @@ impl Cylon {
         ];
         let mut states: Vec<State> = vec![State::Allow, State::Disallow];
 
+        // Retain an owned, matchable copy of every rule for the explicit
+        // RFC 9309 precedence in `allow_rfc9309`.
+        let pattern_rules: Vec<PatternRule> = rules
+            .iter()
+            .map(|rule| PatternRule {
+                allow: matches!(rule, Rule::Allow(..)),
+                pattern: rule.inner().to_string(),
+            })
+            .collect();
+
         rules.sort_by(|a, b| Ord::cmp(a.inner(), b.inner()));
 
         let mut queue = vec![("", 0, 0, State::Intermediate)];
@@ -190,9 +431,290 @@ impl Cylon {
             transitions.push(t);
         }
 
-        Self {
-            states,
-            transitions,
+        Self::from_parts(states, transitions, pattern_rules)
+    }
+
+    /// Merge behaviorally equivalent states via Hopcroft's partition
+    /// refinement, returning a smaller machine that produces identical
+    /// `allow` results. The BFS construction emits many states that
+    /// classify identically (for instance the numerous `[* => 0]` allow
+    /// leaves), and collapsing them shrinks the tables and improves cache
+    /// locality.
+    ///
+    /// The allow-sink (0), disallow-sink (1) and root (2) are kept as
+    /// distinct states so the rest of the machinery can keep addressing
+    /// them by their fixed indices.
+    pub fn minimize(self) -> Self {
+        // Each distinct edge char, plus the MatchAny and MatchEow buckets,
+        // forms one input symbol for the refinement.
+        enum Sym {
+            Char(char),
+            Any,
+            Eow,
+        }
+
+        let mut chars: BTreeSet<char> = BTreeSet::new();
+        for t in &self.transitions {
+            for transition in t {
+                if let Transition(Edge::MatchChar(edge_char), ..) = transition {
+                    chars.insert(*edge_char);
+                }
+            }
+        }
+        let symbols: Vec<Sym> = chars
+            .into_iter()
+            .map(Sym::Char)
+            .chain([Sym::Any, Sym::Eow])
+            .collect();
+
+        let transitions = &self.transitions;
+        let delta = |state: usize, sym: &Sym| -> usize {
+            let t = &transitions[state];
+            // Mirror `Classes::build`/`allow`: the last MatchAny wins, so
+            // a `*` child's override is read rather than the inherited
+            // wildcard state.
+            let any = t
+                .iter()
+                .rev()
+                .find_map(|transition| match transition {
+                    Transition(Edge::MatchAny, next_state) => Some(*next_state),
+                    _ => None,
+                })
+                .unwrap();
+            match sym {
+                Sym::Any => any,
+                Sym::Char(c) => t
+                    .iter()
+                    .find_map(|transition| match transition {
+                        Transition(Edge::MatchChar(edge_char), next_state) if edge_char == c => {
+                            Some(*next_state)
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(any),
+                Sym::Eow => t
+                    .iter()
+                    .find_map(|transition| match transition {
+                        Transition(Edge::MatchEow, next_state) => Some(*next_state),
+                        _ => None,
+                    })
+                    .unwrap_or(any),
+            }
+        };
+
+        let n = self.states.len();
+
+        // Seed the partition with the allow/disallow split, but pin the
+        // three anchor states into singletons so refinement can never
+        // merge them away.
+        let anchors: BTreeSet<usize> = [0usize, 1, 2].iter().copied().filter(|&i| i < n).collect();
+        let mut allow: BTreeSet<usize> = BTreeSet::new();
+        let mut disallow: BTreeSet<usize> = BTreeSet::new();
+        for i in (0..n).filter(|i| !anchors.contains(i)) {
+            match self.states[i] {
+                State::Allow => allow.insert(i),
+                State::Disallow => disallow.insert(i),
+                State::Intermediate => unreachable!(),
+            };
+        }
+
+        let mut p: Vec<BTreeSet<usize>> = anchors
+            .iter()
+            .map(|&i| BTreeSet::from([i]))
+            .chain([allow, disallow])
+            .filter(|set| !set.is_empty())
+            .collect();
+        let mut worklist: Vec<BTreeSet<usize>> = p.clone();
+
+        while let Some(a) = worklist.pop() {
+            for sym in &symbols {
+                // X is the set of states that move into A on this symbol.
+                let x: BTreeSet<usize> = (0..n).filter(|&s| a.contains(&delta(s, sym))).collect();
+                if x.is_empty() {
+                    continue;
+                }
+
+                let mut refined: Vec<BTreeSet<usize>> = Vec::with_capacity(p.len());
+                for y in p.drain(..) {
+                    let inter: BTreeSet<usize> = y.intersection(&x).copied().collect();
+                    let diff: BTreeSet<usize> = y.difference(&x).copied().collect();
+                    if inter.is_empty() || diff.is_empty() {
+                        refined.push(y);
+                        continue;
+                    }
+
+                    // Y splits; update the worklist, always queuing the
+                    // smaller of the two halves (or both, if Y was pending).
+                    if let Some(pos) = worklist.iter().position(|set| *set == y) {
+                        worklist.remove(pos);
+                        worklist.push(inter.clone());
+                        worklist.push(diff.clone());
+                    } else if inter.len() <= diff.len() {
+                        worklist.push(inter.clone());
+                    } else {
+                        worklist.push(diff.clone());
+                    }
+                    refined.push(inter);
+                    refined.push(diff);
+                }
+                p = refined;
+            }
+        }
+
+        // Number the quotient states, giving the anchors their original
+        // indices so 0/1/2 keep their meaning.
+        let mut block_of = vec![0usize; n];
+        for (bi, block) in p.iter().enumerate() {
+            for &s in block {
+                block_of[s] = bi;
+            }
+        }
+        let mut new_id = vec![usize::MAX; p.len()];
+        let mut order: Vec<usize> = vec![];
+        // Reserve ids 0/1/2 for the anchor states, then number the rest.
+        for anchor in anchors.iter().copied() {
+            let b = block_of[anchor];
+            if new_id[b] == usize::MAX {
+                new_id[b] = order.len();
+                order.push(b);
+            }
+        }
+        for (b, id) in new_id.iter_mut().enumerate() {
+            if *id == usize::MAX {
+                *id = order.len();
+                order.push(b);
+            }
+        }
+
+        let states = order
+            .iter()
+            .map(|&b| self.states[*p[b].iter().next().unwrap()])
+            .collect();
+        let transitions = order
+            .iter()
+            .map(|&b| {
+                let rep = *p[b].iter().next().unwrap();
+                self.transitions[rep]
+                    .iter()
+                    .map(|Transition(edge, target)| Transition(*edge, new_id[block_of[*target]]))
+                    .collect()
+            })
+            .collect();
+
+        // Minimization only merges behaviorally equivalent states; the
+        // originating rules (and hence RFC 9309 scoring) are unaffected,
+        // so they carry over verbatim.
+        Self::from_parts(states, transitions, self.rules)
+    }
+
+    /// Match a path using RFC 9309 precedence: when several rules match,
+    /// the one with the longest pattern wins, and `Allow` breaks ties.
+    ///
+    /// The DFA folds overlapping rules together and resolves them by
+    /// prefix-tree construction order, which cannot surface "every rule
+    /// that matches" — a literal branch and a sibling wildcard terminal
+    /// collapse into a single walk. So this scores the path against each
+    /// retained rule independently, tracking the longest matching `Allow`
+    /// and `Disallow` pattern, then compares the two lengths.
+    pub fn allow_rfc9309(&self, path: &str) -> bool {
+        let mut allow_len = 0;
+        let mut disallow_len = 0;
+
+        for rule in &self.rules {
+            if Self::pattern_matches(&rule.pattern, path) {
+                let len = rule.pattern.chars().count();
+                if rule.allow {
+                    allow_len = allow_len.max(len);
+                } else {
+                    disallow_len = disallow_len.max(len);
+                }
+            }
+        }
+
+        // Allow wins ties; with nothing matched the path is allowed.
+        allow_len >= disallow_len
+    }
+
+    /// Whether a robots.txt `pattern` matches `path`. `*` matches any run
+    /// of characters and a trailing `$` anchors the end of the path;
+    /// otherwise a pattern matches any path it is a prefix of. This is
+    /// the same wildcard/EoW semantics the DFA implements, evaluated
+    /// against a single rule for RFC 9309 scoring.
+    fn pattern_matches(pattern: &str, path: &str) -> bool {
+        // A plain prefix match is a full glob match once an implicit
+        // trailing wildcard is appended; `$` drops that wildcard so the
+        // whole path must be consumed.
+        let glob: Vec<char> = match pattern.strip_suffix('$') {
+            Some(rest) => rest.chars().collect(),
+            None => pattern.chars().chain(std::iter::once('*')).collect(),
+        };
+        let path: Vec<char> = path.chars().collect();
+
+        // Iterative wildcard matcher with backtracking to the last `*`.
+        let (mut p, mut s) = (0usize, 0usize);
+        let (mut star, mut resume) = (None, 0usize);
+        while s < path.len() {
+            if p < glob.len() && glob[p] == '*' {
+                star = Some(p);
+                resume = s;
+                p += 1;
+            } else if p < glob.len() && glob[p] == path[s] {
+                p += 1;
+                s += 1;
+            } else if let Some(sp) = star {
+                p = sp + 1;
+                resume += 1;
+                s = resume;
+            } else {
+                return false;
+            }
+        }
+        while p < glob.len() && glob[p] == '*' {
+            p += 1;
+        }
+        p == glob.len()
+    }
+}
+
+/// A resumable driver over a [`Cylon`] that consumes a path one
+/// character at a time. It holds the current state index so callers can
+/// feed a URL path as it is decoded, or clone the `Matcher` at a branch
+/// point to test many candidate paths that share a long common prefix
+/// without re-walking that prefix each time.
+#[derive(Clone)]
+pub struct Matcher<'a> {
+    cylon: &'a Cylon,
+    state: usize,
+}
+
+impl<'a> Matcher<'a> {
+    /// Advance the automaton by one character.
+    pub fn push(&mut self, path_char: char) {
+        self.state = self.cylon.classes.step(self.state, path_char);
+    }
+
+    /// Return whether the path consumed so far is allowed, applying the
+    /// trailing EoW transition without mutating the matcher's state.
+    pub fn allowed(&self) -> bool {
+        // Follow the EoW transition, if necessary
+        let t = &self.cylon.transitions[self.state];
+        let state = t
+            .iter()
+            .rev()
+            .find(|transition| {
+                matches!(
+                    transition,
+                    Transition(Edge::MatchEow, ..) | Transition(Edge::MatchAny, ..)
+                )
+            })
+            .map(|Transition(.., next_state)| *next_state)
+            .unwrap_or(self.state);
+
+        match self.cylon.states[state] {
+            State::Allow => true,
+            State::Disallow => false,
+            // Intermediate states are not preserved in the DFA
+            State::Intermediate => unreachable!(),
         }
     }
 }
@@ -438,6 +960,170 @@ mod tests {
         assert_eq!(true, machine.allow("/www/public/images"));
     }
 
+    #[test]
+    fn test_matcher_clone_shares_prefix() {
+        let rules = vec![
+            Rule::Disallow("/"),
+            Rule::Allow("/abc"),
+            Rule::Allow("/abd"),
+        ];
+
+        let machine = Cylon::compile(rules);
+
+        // Walk the shared "/ab" prefix once, then branch.
+        let mut prefix = machine.matcher();
+        for path_char in "/ab".chars() {
+            prefix.push(path_char);
+        }
+
+        let mut abc = prefix.clone();
+        abc.push('c');
+        let mut abd = prefix.clone();
+        abd.push('d');
+        let mut abe = prefix;
+        abe.push('e');
+
+        assert_eq!(true, abc.allowed());
+        assert_eq!(true, abd.allowed());
+        assert_eq!(false, abe.allowed());
+    }
+
+    #[test]
+    fn test_allow_rfc9309_longest_match() {
+        // Overlapping wildcard rules: the longest matching pattern wins,
+        // so the disallow (a longer literal) beats the shorter "/folder/*".
+        let machine = Cylon::compile(vec![
+            Rule::Allow("/folder/*"),
+            Rule::Disallow("/folder/sub/file"),
+        ]);
+        assert_eq!(false, machine.allow_rfc9309("/folder/sub/file"));
+        assert_eq!(true, machine.allow_rfc9309("/folder/other"));
+
+        // A wildcard Allow and a same-or-shorter literal Disallow both
+        // match: equal lengths break in favor of Allow, and the literal
+        // branch must not hide the wildcard terminal.
+        let machine = Cylon::compile(vec![Rule::Allow("/a/*"), Rule::Disallow("/a/b")]);
+        assert_eq!(true, machine.allow_rfc9309("/a/b"));
+
+        // Two identical patterns with opposite verdicts: Allow wins.
+        let machine = Cylon::compile(vec![Rule::Disallow("/a/*"), Rule::Allow("/a/*")]);
+        assert_eq!(true, machine.allow_rfc9309("/a/b"));
+
+        // With nothing matching, the path is allowed.
+        let machine = Cylon::compile(vec![Rule::Disallow("/private")]);
+        assert_eq!(true, machine.allow_rfc9309("/public"));
+        assert_eq!(false, machine.allow_rfc9309("/private"));
+    }
+
+    #[test]
+    fn test_minimize_preserves_allow() {
+        let rules = vec![
+            Rule::Allow("/"),
+            Rule::Disallow("/a$"),
+            Rule::Disallow("/abc"),
+            Rule::Allow("/abc/*"),
+            Rule::Disallow("/foo/bar"),
+            Rule::Allow("/*/bar"),
+            Rule::Disallow("/www/*/images"),
+            Rule::Allow("/www/public/images"),
+        ];
+
+        let machine = Cylon::compile(rules);
+        let minimized = Cylon::compile(vec![
+            Rule::Allow("/"),
+            Rule::Disallow("/a$"),
+            Rule::Disallow("/abc"),
+            Rule::Allow("/abc/*"),
+            Rule::Disallow("/foo/bar"),
+            Rule::Allow("/*/bar"),
+            Rule::Disallow("/www/*/images"),
+            Rule::Allow("/www/public/images"),
+        ])
+        .minimize();
+
+        // Merging equivalent states must not change any verdict.
+        for path in [
+            "/",
+            "/directory",
+            "/a",
+            "/ab",
+            "/abc",
+            "/abc/123",
+            "/foo",
+            "/foo/bar",
+            "/baz/bar",
+            "/www/cat/images",
+            "/www/public/images",
+        ] {
+            assert_eq!(machine.allow(path), minimized.allow(path), "path: {}", path);
+        }
+
+        // ...but it must actually collapse the identical leaves.
+        assert!(minimized.states.len() < machine.states.len());
+    }
+
+    #[test]
+    fn test_minimize_preserves_rfc9309() {
+        // Overlapping rules of differing pattern length: behaviorally
+        // identical terminals must not be merged in a way that changes
+        // the RFC 9309 longest-match verdict.
+        let rules = || {
+            vec![
+                Rule::Disallow("/"),
+                Rule::Allow("/a/*"),
+                Rule::Disallow("/a/b"),
+                Rule::Allow("/a/b/c"),
+                Rule::Disallow("/folder/sub/file"),
+                Rule::Allow("/folder/*"),
+            ]
+        };
+
+        let machine = Cylon::compile(rules());
+        let minimized = Cylon::compile(rules()).minimize();
+
+        for path in [
+            "/",
+            "/a/b",
+            "/a/b/c",
+            "/a/other",
+            "/folder/sub/file",
+            "/folder/other",
+        ] {
+            assert_eq!(
+                machine.allow_rfc9309(path),
+                minimized.allow_rfc9309(path),
+                "path: {}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_allow_url_normalization() {
+        let machine = Cylon::compile(vec![Rule::Disallow("/"), Rule::Allow("/abc")]);
+
+        // Percent-encoded unreserved octets decode before matching.
+        assert_eq!(true, machine.allow_url("/a%62c"));
+        // A missing leading slash is inserted.
+        assert_eq!(true, machine.allow_url("abc"));
+        // %2F stays distinct from '/', so it does not match "/abc".
+        assert_eq!(false, machine.allow_url("/%2Fabc"));
+
+        let machine = Cylon::compile(vec![Rule::Disallow("/"), Rule::Allow("/a/b")]);
+        assert_eq!(true, machine.allow_url("/a/b"));
+        // Encoded slash is not decoded, so this does not reach "/a/b".
+        assert_eq!(false, machine.allow_url("/a%2fb"));
+        // `.`/`..` segments are collapsed before matching.
+        assert_eq!(true, machine.allow_url("/a/./b"));
+        assert_eq!(true, machine.allow_url("/a/c/../b"));
+        assert_eq!(false, machine.allow_url("/a/b/.."));
+
+        // Interior empty segments are preserved, not collapsed like `.`.
+        let machine = Cylon::compile(vec![Rule::Disallow("/"), Rule::Allow("/a//b")]);
+        assert_eq!(true, machine.allow_url("/a//b"));
+        assert_eq!(false, machine.allow_url("/a/b"));
+    }
+
     #[test]
     fn test_matches() {
         // Test cases from:
@@ -0,0 +1,5 @@
+mod dfa;
+mod parse;
+
+pub use crate::dfa::{Cylon, Matcher, Rule};
+pub use crate::parse::{Agent, Robots};
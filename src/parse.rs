@@ -0,0 +1,223 @@
+use crate::dfa::{Cylon, Rule};
+
+/// A group of `Allow`/`Disallow` directives that share one or more
+/// user-agent product tokens, compiled down to a single `Cylon`.
+struct Group {
+    // Lower-cased product tokens that select this group. The special
+    // token "*" marks the fallback group.
+    agents: Vec<String>,
+    cylon: Cylon,
+    crawl_delay: Option<f64>,
+}
+
+/// The result of looking up a product token in a parsed robots.txt
+/// document: the `Cylon` that governs that agent together with the
+/// group's crawl-delay and the file-level list of sitemaps.
+pub struct Agent<'a> {
+    pub cylon: &'a Cylon,
+    pub crawl_delay: Option<f64>,
+    pub sitemaps: &'a [String],
+}
+
+/// A parsed robots.txt document. `User-agent:` lines act as group
+/// headers (much like `[section]` headers in an INI file); the
+/// `Allow:`/`Disallow:` directives beneath each header are accumulated
+/// into that group and compiled into a `Cylon`. Directives that do not
+/// belong to any group, such as `Sitemap:`, are collected at the file
+/// level.
+pub struct Robots {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+}
+
+impl Robots {
+    /// Parse a robots.txt document into its per-user-agent groups.
+    pub fn parse(robots_txt: &str) -> Self {
+        let mut groups: Vec<Group> = vec![];
+        let mut sitemaps: Vec<String> = vec![];
+
+        // Accumulators for the group currently being read. `seen_rule`
+        // tracks whether we have read a group directive (`Allow`,
+        // `Disallow` or `Crawl-delay`) since the last header,
+        // so that consecutive `User-agent:` lines merge into one shared
+        // group instead of closing the previous one.
+        let mut agents: Vec<String> = vec![];
+        let mut rules: Vec<Rule> = vec![];
+        let mut crawl_delay: Option<f64> = None;
+        let mut seen_rule = false;
+
+        for line in robots_txt.lines() {
+            // Strip `#` comments and surrounding whitespace.
+            let line = match line.find('#') {
+                Some(i) => &line[..i],
+                None => line,
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            let key = key.to_ascii_lowercase();
+
+            // Directives that appear before the first `User-agent:` header
+            // belong to no group and cannot be selected by `lookup`, so we
+            // drop them rather than building an unreachable group. File-level
+            // directives such as `Sitemap:` are still honored below.
+            if agents.is_empty() && matches!(key.as_str(), "allow" | "disallow" | "crawl-delay") {
+                continue;
+            }
+
+            match key.as_str() {
+                "user-agent" => {
+                    // A header following one or more rules starts a new
+                    // group; a header following another header just adds
+                    // its token to the shared group.
+                    if seen_rule {
+                        groups.push(Group {
+                            agents: std::mem::take(&mut agents),
+                            cylon: Cylon::compile(std::mem::take(&mut rules)),
+                            crawl_delay: crawl_delay.take(),
+                        });
+                        seen_rule = false;
+                    }
+                    agents.push(value.to_ascii_lowercase());
+                }
+                "allow" => {
+                    rules.push(Rule::Allow(value));
+                    seen_rule = true;
+                }
+                "disallow" => {
+                    rules.push(Rule::Disallow(value));
+                    seen_rule = true;
+                }
+                "crawl-delay" => {
+                    crawl_delay = value.parse().ok();
+                    seen_rule = true;
+                }
+                "sitemap" => sitemaps.push(value.to_owned()),
+                // Unknown directives are ignored, as the spec requires.
+                _ => {}
+            }
+        }
+
+        if !agents.is_empty() {
+            groups.push(Group {
+                agents,
+                cylon: Cylon::compile(rules),
+                crawl_delay,
+            });
+        }
+
+        Self { groups, sitemaps }
+    }
+
+    /// Select the group governing `agent` and return its `Cylon`,
+    /// crawl-delay and the file-level sitemap list.
+    ///
+    /// Matching is case-insensitive and picks the group whose user-agent
+    /// token is the longest prefix of `agent`, falling back to the `*`
+    /// group when no token matches. Returns `None` only when neither a
+    /// matching token nor a `*` group is present.
+    pub fn lookup(&self, agent: &str) -> Option<Agent> {
+        let agent = agent.to_ascii_lowercase();
+
+        let mut best: Option<&Group> = None;
+        let mut best_len = 0;
+        let mut fallback: Option<&Group> = None;
+
+        for group in &self.groups {
+            for token in &group.agents {
+                if token == "*" {
+                    fallback = Some(group);
+                } else if agent.starts_with(token.as_str()) && token.len() >= best_len {
+                    best_len = token.len();
+                    best = Some(group);
+                }
+            }
+        }
+
+        best.or(fallback).map(|group| Agent {
+            cylon: &group.cylon,
+            crawl_delay: group.crawl_delay,
+            sitemaps: &self.sitemaps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# a comment
+User-agent: googlebot
+User-agent: bingbot
+Disallow: /private
+Allow: /private/public
+Crawl-delay: 5
+
+User-agent: *
+Disallow: /
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn test_merges_consecutive_user_agents() {
+        let robots = Robots::parse(ROBOTS);
+
+        let google = robots.lookup("googlebot").unwrap();
+        let bing = robots.lookup("bingbot").unwrap();
+        assert_eq!(Some(5.0), google.crawl_delay);
+        assert_eq!(Some(5.0), bing.crawl_delay);
+        assert_eq!(false, google.cylon.allow("/private"));
+        assert_eq!(true, google.cylon.allow("/private/public"));
+        assert_eq!(true, bing.cylon.allow("/private/public"));
+    }
+
+    #[test]
+    fn test_longest_prefix_match_with_fallback() {
+        let robots = Robots::parse(ROBOTS);
+
+        // "googlebot-news" matches the "googlebot" group by prefix.
+        let news = robots.lookup("googlebot-news").unwrap();
+        assert_eq!(true, news.cylon.allow("/private/public"));
+
+        // An unknown agent falls back to the "*" group.
+        let other = robots.lookup("randombot").unwrap();
+        assert_eq!(false, other.cylon.allow("/anything"));
+        assert_eq!(None, other.crawl_delay);
+    }
+
+    #[test]
+    fn test_directives_before_first_header_are_ignored() {
+        let robots = Robots::parse(
+            "\
+Disallow: /x
+User-agent: *
+Disallow: /y
+",
+        );
+
+        // The pre-header `Disallow: /x` belongs to no group and is
+        // dropped; only the `*` group's own rule applies.
+        let agent = robots.lookup("anybot").unwrap();
+        assert_eq!(true, agent.cylon.allow("/x"));
+        assert_eq!(false, agent.cylon.allow("/y"));
+    }
+
+    #[test]
+    fn test_file_level_sitemaps() {
+        let robots = Robots::parse(ROBOTS);
+        let agent = robots.lookup("googlebot").unwrap();
+        assert_eq!(
+            &["https://example.com/sitemap.xml".to_owned()],
+            agent.sitemaps,
+        );
+    }
+}